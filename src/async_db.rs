@@ -0,0 +1,821 @@
+//! an async mirror of [`crate::db`], built on `tokio::fs` instead of
+//! `std::fs`, so a future daemon/server mode can service many concurrent
+//! readers without a thread per request. the on-disk format, the
+//! index/search parsing, and the advisory locking primitive are shared
+//! with the sync `FsDb` (see the `crate::db` imports below); what
+//! changes is how the bytes get read.
+//!
+//! the OS advisory lock (`fs2`) and the small sidecar files
+//! (`<id>.meta`, `meta.toml`, `origin`) have no async equivalent, so
+//! those few operations run on the blocking thread pool via
+//! [`blocking`] rather than on the async executor. the bulk of the
+//! work -- walking issue/comment directories and reading their content
+//! -- uses real `tokio::fs` I/O, and `get_issues`/`get_issue_comments`
+//! overlap up to `LIST_CONCURRENCY` of those reads at once instead of
+//! doing them one at a time.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::SystemTime;
+
+use fs2::FileExt;
+use futures::stream::{self, Stream, StreamExt};
+use async_stream::stream as gen_stream;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader as AsyncBufReader};
+use tokio::sync::RwLock as AsyncRwLock;
+
+use crate::db::{
+    Comment, FsDbInner, FsError, Issue, IssueFilter, IssueStatus, LockMode, Posting, SearchHit,
+    SyncMeta, content_hash, index_content, read_counts, read_issue_meta, read_or_create_origin_id,
+    read_search_index, read_sync_meta, tokenize, write_index, write_issue_meta, write_sync_meta,
+};
+
+/// how many issue/comment file reads `get_issues`/`get_issue_comments`
+/// keep in flight at once
+const LIST_CONCURRENCY : usize = 16;
+
+pub type BoxStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+
+/// run a blocking (`std::fs`, `fs2`) operation on tokio's blocking
+/// thread pool, so it can't stall the async executor
+async fn blocking<T, F>(f : F) -> Result<T, FsError>
+where
+    T : Send + 'static,
+    F : FnOnce() -> Result<T, FsError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.expect("blocking task panicked")
+}
+
+/// an RAII guard over an OS advisory lock on `index.lock`, acquired on
+/// the blocking thread pool and released synchronously on drop --
+/// `Drop` can't `.await`, so unlock reuses the same fire-and-forget
+/// call as `db::FileLock`
+struct AsyncFileLock {
+    file : File,
+}
+
+impl AsyncFileLock {
+    async fn shared(file : &File) -> Result<Self, FsError> {
+        let file = file.try_clone()?;
+        let file = blocking(move || { file.lock_shared()?; Ok(file) }).await?;
+        Ok(Self{file})
+    }
+
+    async fn exclusive(file : &File) -> Result<Self, FsError> {
+        let file = file.try_clone()?;
+        let file = blocking(move || { file.lock_exclusive()?; Ok(file) }).await?;
+        Ok(Self{file})
+    }
+}
+
+impl Drop for AsyncFileLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// the async counterpart to `db::Db`: the same operations, but methods
+/// return futures and the listing methods return bounded-concurrency
+/// streams instead of blocking iterators
+#[allow(async_fn_in_trait)] // only ever used concretely against `AsyncFsDb`, never as `dyn AsyncDb`
+pub trait AsyncDb {
+    type Error : std::error::Error;
+
+    async fn new_issue(&self, first_comment : &str) -> Result<u32, Self::Error>;
+    async fn new_comment(&self, issue_id : u32, content : &str) -> Result<u32, Self::Error>;
+
+    /// list issues matching `filter`, overlapping up to `LIST_CONCURRENCY`
+    /// file reads instead of reading one issue at a time
+    fn get_issues<'a>(&'a self, filter : &IssueFilter) -> BoxStream<'a, Result<Issue, Self::Error>>;
+    async fn get_issue(&self, issue_id : u32) -> Result<Issue, Self::Error>;
+    fn get_issue_comments<'a>(&'a self, issue_id : u32) -> BoxStream<'a, Result<Comment, Self::Error>>;
+    async fn get_issue_comment(&self, issue_id : u32, comment_id : u32) -> Result<Comment, Self::Error>;
+
+    async fn set_status(&self, issue_id : u32, status : IssueStatus) -> Result<(), Self::Error>;
+    async fn add_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error>;
+    async fn remove_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error>;
+    async fn assign(&self, issue_id : u32, assignee : Option<String>) -> Result<(), Self::Error>;
+
+    async fn search(&self, query : &str, any : bool) -> Result<Vec<SearchHit>, Self::Error>;
+}
+
+pub struct AsyncFsDb {
+    inner : AsyncRwLock<FsDbInner>,
+    lock_file : File,
+    mode : LockMode,
+}
+
+impl AsyncFsDb {
+    pub async fn new(path : PathBuf, mode : LockMode) -> Result<Self, FsError> {
+        let path = path.into_boxed_path();
+
+        let lock_file = {
+            let lock_path = path.join("index.lock");
+            blocking(move || {
+                OpenOptions::new().create(true).truncate(false).write(true).open(lock_path).map_err(FsError::from)
+            }).await?
+        };
+
+        // mirrors the sync FsDb::new: a db that already has an index.txt
+        // is just being read, so take the shared lock and never contend
+        // with another process's exclusive section; only a first-time
+        // scan, which has to write index.txt/search.idx/origin, needs
+        // the exclusive lock -- and only when `mode` permits writing them.
+        let has_index = {
+            let index_path = path.join("index.txt");
+            blocking(move || Ok(index_path.exists())).await?
+        };
+
+        let inner = if has_index {
+            let _flock = AsyncFileLock::shared(&lock_file).await?;
+            read_index(path, mode).await?
+        } else {
+            let _flock = AsyncFileLock::exclusive(&lock_file).await?;
+
+            // re-check: another process may have finished its own
+            // first-time scan while we waited for the exclusive lock
+            let has_index = {
+                let index_path = path.join("index.txt");
+                blocking(move || Ok(index_path.exists())).await?
+            };
+
+            if has_index {
+                read_index(path, mode).await?
+            } else {
+                create_index(path, mode).await?
+            }
+        };
+
+        Ok(Self{inner : AsyncRwLock::new(inner), lock_file, mode})
+    }
+
+    pub async fn save_index(&self) -> Result<(), FsError> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = AsyncFileLock::exclusive(&self.lock_file).await?;
+        let db = self.inner.read().await;
+        let (path, issue_count, comment_count, search_index) = snapshot_for_persist(&db);
+        drop(db);
+
+        persist_index(path, issue_count, comment_count, search_index).await
+    }
+}
+
+async fn read_index(path : Box<Path>, mode : LockMode) -> Result<FsDbInner, FsError> {
+    let (issue_count, comment_count) = {
+        let index_path = path.join("index.txt");
+        blocking(move || read_counts(&index_path)).await?
+    };
+
+    let search_index = {
+        let path = path.clone();
+        blocking(move || read_search_index(&path)).await?
+    };
+
+    let origin_id = {
+        let path = path.to_path_buf();
+        blocking(move || read_or_create_origin_id(&path, mode)).await?
+    };
+
+    Ok(FsDbInner{path, issue_count, comment_count, search_index, origin_id})
+}
+
+/// scan `path` from scratch, backfilling `search_index` and (in
+/// `ReadWrite` mode only) the `origin` file, missing `.meta` sidecars,
+/// `index.txt`, and `search.idx`, mirroring the sync
+/// `FsDbInner::create_index`
+async fn create_index(path : Box<Path>, mode : LockMode) -> Result<FsDbInner, FsError> {
+    let origin_id = {
+        let path = path.to_path_buf();
+        blocking(move || read_or_create_origin_id(&path, mode)).await?
+    };
+
+    let mut issue_count = 0;
+    let mut comment_count = HashMap::new();
+    let mut search_index = HashMap::new();
+
+    let mut issues = tokio::fs::read_dir(&path).await?;
+
+    while let Some(issue) = issues.next_entry().await? {
+        let name = issue.file_name();
+        if name == "index.txt" || name == "search.idx" || name == "index.lock" || name == "origin" {
+            continue
+        }
+
+        let issue_id : u32 = name
+            .to_str().ok_or(FsError::BadDb)?
+            .parse().map_err(|_| FsError::BadDb)?;
+
+        issue_count = issue_count.max(issue_id+1);
+
+        let mut max_comment = 0;
+        let mut comments = tokio::fs::read_dir(issue.path()).await?;
+
+        while let Some(comment) = comments.next_entry().await? {
+            let name = comment.file_name();
+            let name = name.to_str().ok_or(FsError::BadDb)?;
+            if name.ends_with(".meta") || name == "meta.toml" {
+                continue
+            }
+
+            let comment_id : u32 = name.parse().map_err(|_| FsError::BadDb)?;
+            max_comment = max_comment.max(comment_id+1);
+
+            let comment_path = comment.path();
+            let content = tokio::fs::read_to_string(&comment_path).await?;
+            index_content(&mut search_index, issue_id, comment_id, &content);
+
+            let existing = {
+                let comment_path = comment_path.clone();
+                blocking(move || read_sync_meta(&comment_path)).await?
+            };
+
+            if mode == LockMode::ReadWrite && existing.is_none() {
+                let fs_meta = tokio::fs::metadata(&comment_path).await?;
+                let created = fs_meta.created()?;
+                let modified = fs_meta.modified()?;
+
+                let meta = SyncMeta{
+                    hash : content_hash(&content, created, origin_id),
+                    origin_id,
+                    created,
+                    modified,
+                };
+
+                blocking(move || write_sync_meta(&comment_path, &meta)).await?;
+            }
+        }
+
+        comment_count.insert(issue_id, max_comment);
+    }
+
+    let ret = FsDbInner{path, issue_count, comment_count, search_index, origin_id};
+
+    if mode == LockMode::ReadWrite {
+        let path = ret.path.clone();
+        let issue_count = ret.issue_count;
+        let comment_count = ret.comment_count.clone();
+        let search_index = ret.search_index.clone();
+        blocking(move || write_index(&path, issue_count, &comment_count, &search_index)).await?;
+    }
+
+    Ok(ret)
+}
+
+/// re-read `issue_count`/`comment_count`/`search_index` from disk,
+/// picking up writes made by other processes since `db` was loaded.
+/// mirrors the sync `FsDbInner::refresh_counts`: called after taking
+/// the exclusive lock and before allocating a new id or folding a new
+/// comment into the index, so a concurrent writer's postings aren't
+/// clobbered by a stale in-memory index on the next persist.
+async fn refresh_counts(db : &mut FsDbInner) -> Result<(), FsError> {
+    let index_path = db.path.join("index.txt");
+    let (issue_count, comment_count) = blocking(move || read_counts(&index_path)).await?;
+    db.issue_count = issue_count;
+    db.comment_count = comment_count;
+
+    let path = db.path.clone();
+    db.search_index = blocking(move || read_search_index(&path)).await?;
+
+    Ok(())
+}
+
+/// the fields `persist_index` needs, cloned out of an `FsDbInner`
+type PersistSnapshot = (Box<Path>, u32, HashMap<u32, u32>, HashMap<String, Vec<Posting>>);
+
+/// clone the fields `persist_index` needs out of `db`. A plain (non-async)
+/// function so callers can call it, then either `drop` their lock guard
+/// right away (`save_index`) or keep it held across the `persist_index`
+/// call that follows (`new_issue`/`new_comment`, for atomicity)
+fn snapshot_for_persist(db : &FsDbInner) -> PersistSnapshot {
+    (db.path.clone(), db.issue_count, db.comment_count.clone(), db.search_index.clone())
+}
+
+/// persist a snapshot's `issue_count`/`comment_count`/`search_index` to
+/// `index.txt`/`search.idx`. Takes owned copies (see `snapshot_for_persist`)
+/// rather than `&FsDbInner` so callers that don't need the atomicity below
+/// can drop their lock guard before awaiting the write.
+///
+/// callers that pair this with an id-allocating read-modify-write (e.g.
+/// `new_issue`/`new_comment`) must instead keep their exclusive lock
+/// guard held across this call, so the allocation and the persist are
+/// one atomic section
+async fn persist_index(
+    path : Box<Path>,
+    issue_count : u32,
+    comment_count : HashMap<u32, u32>,
+    search_index : HashMap<String, Vec<Posting>>,
+) -> Result<(), FsError> {
+    blocking(move || write_index(&path, issue_count, &comment_count, &search_index)).await
+}
+
+/// fetch and filter a single issue for `get_issues`'s bounded stream;
+/// `Ok(None)` means "skip" (missing file, or excluded by `filter`)
+async fn read_issue(base : &Path, issue_id : u32, filter : &IssueFilter) -> Result<Option<Issue>, FsError> {
+    let issue_path = base.join(issue_id.to_string());
+    let content_path = issue_path.join("0");
+
+    let file = match tokio::fs::File::open(&content_path).await {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let fs_meta = file.metadata().await?;
+
+    let issue_meta = {
+        let issue_path = issue_path.clone();
+        blocking(move || read_issue_meta(&issue_path)).await?
+    };
+
+    if !filter.matches(&issue_meta) {
+        return Ok(None);
+    }
+
+    let mut buf = String::new();
+    AsyncBufReader::new(file).read_line(&mut buf).await?;
+
+    let (created, modified) = {
+        let content_path = content_path.clone();
+        match blocking(move || read_sync_meta(&content_path)).await? {
+            Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+            None => (fs_meta.created()?, fs_meta.modified()?),
+        }
+    };
+
+    Ok(Some(Issue{
+        issue_id,
+        created,
+        modified,
+        status : issue_meta.status,
+        labels : issue_meta.labels,
+        assignee : issue_meta.assignee,
+        content : buf,
+    }))
+}
+
+/// fetch a single comment for `get_issue_comments`'s bounded stream;
+/// `Ok(None)` means "skip" (missing file)
+async fn read_comment(base : &Path, issue_id : u32, comment_id : u32) -> Result<Option<Comment>, FsError> {
+    let mut path = base.join(issue_id.to_string());
+    path.push(comment_id.to_string());
+
+    let content = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let fs_meta = tokio::fs::metadata(&path).await?;
+
+    let (created, modified) = {
+        let path = path.clone();
+        match blocking(move || read_sync_meta(&path)).await? {
+            Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+            None => (fs_meta.created()?, fs_meta.modified()?),
+        }
+    };
+
+    Ok(Some(Comment{
+        issue_id,
+        comment_id,
+        created,
+        modified,
+        content,
+    }))
+}
+
+impl AsyncDb for AsyncFsDb {
+    type Error = FsError;
+
+    async fn new_issue(&self, first_comment : &str) -> Result<u32, Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = AsyncFileLock::exclusive(&self.lock_file).await?;
+        let mut db = self.inner.write().await;
+        refresh_counts(&mut db).await?;
+
+        let issue_id = db.issue_count;
+        let issue_path = db.path.join(issue_id.to_string());
+
+        tokio::fs::create_dir(&issue_path).await?;
+
+        db.comment_count.insert(issue_id, 1);
+        db.issue_count += 1;
+        index_content(&mut db.search_index, issue_id, 0, first_comment);
+
+        let content_path = issue_path.join("0");
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(&content_path)
+            .await?;
+        file.write_all(first_comment.as_bytes()).await?;
+
+        let now = SystemTime::now();
+        let meta = SyncMeta{
+            hash : content_hash(first_comment, now, db.origin_id),
+            origin_id : db.origin_id,
+            created : now,
+            modified : now,
+        };
+
+        blocking(move || write_sync_meta(&content_path, &meta)).await?;
+
+        // persist while the exclusive lock is still held, so the id
+        // allocation and the index persist are one atomic section
+        let (path, issue_count, comment_count, search_index) = snapshot_for_persist(&db);
+        persist_index(path, issue_count, comment_count, search_index).await?;
+
+        Ok(issue_id)
+    }
+
+    async fn new_comment(&self, issue_id : u32, content : &str) -> Result<u32, Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = AsyncFileLock::exclusive(&self.lock_file).await?;
+        let mut db = self.inner.write().await;
+        refresh_counts(&mut db).await?;
+
+        let comment_id = *db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+
+        let mut path = db.path.join(issue_id.to_string());
+        path.push(comment_id.to_string());
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(&path)
+            .await?;
+        file.write_all(content.as_bytes()).await?;
+
+        *db.comment_count.get_mut(&issue_id).unwrap() += 1;
+        index_content(&mut db.search_index, issue_id, comment_id, content);
+
+        let now = SystemTime::now();
+        let meta = SyncMeta{
+            hash : content_hash(content, now, db.origin_id),
+            origin_id : db.origin_id,
+            created : now,
+            modified : now,
+        };
+
+        blocking(move || write_sync_meta(&path, &meta)).await?;
+
+        // see new_issue: persist while still holding the exclusive lock
+        let (path, issue_count, comment_count, search_index) = snapshot_for_persist(&db);
+        persist_index(path, issue_count, comment_count, search_index).await?;
+
+        Ok(comment_id)
+    }
+
+    async fn set_status(&self, issue_id : u32, status : IssueStatus) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = AsyncFileLock::exclusive(&self.lock_file).await?;
+        let db = self.inner.read().await;
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+        let issue_path = db.path.join(issue_id.to_string());
+
+        blocking(move || {
+            let mut meta = read_issue_meta(&issue_path)?;
+            meta.status = status;
+            write_issue_meta(&issue_path, &meta)
+        }).await
+    }
+
+    async fn add_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = AsyncFileLock::exclusive(&self.lock_file).await?;
+        let db = self.inner.read().await;
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+        let issue_path = db.path.join(issue_id.to_string());
+        let label = label.to_string();
+
+        blocking(move || {
+            let mut meta = read_issue_meta(&issue_path)?;
+            if !meta.labels.iter().any(|l| l == &label) {
+                meta.labels.push(label);
+            }
+            write_issue_meta(&issue_path, &meta)
+        }).await
+    }
+
+    async fn remove_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = AsyncFileLock::exclusive(&self.lock_file).await?;
+        let db = self.inner.read().await;
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+        let issue_path = db.path.join(issue_id.to_string());
+        let label = label.to_string();
+
+        blocking(move || {
+            let mut meta = read_issue_meta(&issue_path)?;
+            meta.labels.retain(|l| l != &label);
+            write_issue_meta(&issue_path, &meta)
+        }).await
+    }
+
+    async fn assign(&self, issue_id : u32, assignee : Option<String>) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = AsyncFileLock::exclusive(&self.lock_file).await?;
+        let db = self.inner.read().await;
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+        let issue_path = db.path.join(issue_id.to_string());
+
+        blocking(move || {
+            let mut meta = read_issue_meta(&issue_path)?;
+            meta.assignee = assignee;
+            write_issue_meta(&issue_path, &meta)
+        }).await
+    }
+
+    fn get_issues<'a>(&'a self, filter : &IssueFilter) -> BoxStream<'a, Result<Issue, Self::Error>> {
+        let filter = filter.clone();
+
+        Box::pin(gen_stream! {
+            let _flock = match AsyncFileLock::shared(&self.lock_file).await {
+                Ok(flock) => flock,
+                Err(err) => { yield Err(err); return; },
+            };
+
+            let db = self.inner.read().await;
+            let issue_count = db.issue_count;
+            let base = db.path.to_path_buf();
+            drop(db);
+
+            let mut reads = stream::iter(0..issue_count)
+                .map(|issue_id| {
+                    let base = base.clone();
+                    let filter = filter.clone();
+                    async move { read_issue(&base, issue_id, &filter).await }
+                })
+                .buffered(LIST_CONCURRENCY);
+
+            while let Some(res) = reads.next().await {
+                match res {
+                    Ok(Some(issue)) => yield Ok(issue),
+                    Ok(None) => {},
+                    Err(err) => yield Err(err),
+                }
+            }
+        })
+    }
+
+    async fn get_issue(&self, issue_id : u32) -> Result<Issue, Self::Error> {
+        let _flock = AsyncFileLock::shared(&self.lock_file).await?;
+        let db = self.inner.read().await;
+        let issue_path = db.path.join(issue_id.to_string());
+        drop(db);
+
+        let content_path = issue_path.join("0");
+
+        let mut file = tokio::fs::File::open(&content_path).await?;
+        let fs_meta = file.metadata().await?;
+
+        let mut buf = String::new();
+        file.read_to_string(&mut buf).await?;
+
+        let (created, modified) = {
+            let content_path = content_path.clone();
+            match blocking(move || read_sync_meta(&content_path)).await? {
+                Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+                None => (fs_meta.created()?, fs_meta.modified()?),
+            }
+        };
+
+        let issue_meta = blocking(move || read_issue_meta(&issue_path)).await?;
+
+        Ok(Issue{
+            issue_id,
+            created,
+            modified,
+            status : issue_meta.status,
+            labels : issue_meta.labels,
+            assignee : issue_meta.assignee,
+            content : buf,
+        })
+    }
+
+    fn get_issue_comments<'a>(&'a self, issue_id : u32) -> BoxStream<'a, Result<Comment, Self::Error>> {
+        Box::pin(gen_stream! {
+            let _flock = match AsyncFileLock::shared(&self.lock_file).await {
+                Ok(flock) => flock,
+                Err(err) => { yield Err(err); return; },
+            };
+
+            let db = self.inner.read().await;
+            let count = match db.comment_count.get(&issue_id) {
+                Some(n) => *n,
+                None => { yield Err(FsError::NoIssue(issue_id)); return; },
+            };
+            let base = db.path.to_path_buf();
+            drop(db);
+
+            let mut reads = stream::iter(0..count)
+                .map(|comment_id| {
+                    let base = base.clone();
+                    async move { read_comment(&base, issue_id, comment_id).await }
+                })
+                .buffered(LIST_CONCURRENCY);
+
+            while let Some(res) = reads.next().await {
+                match res {
+                    Ok(Some(comment)) => yield Ok(comment),
+                    Ok(None) => {},
+                    Err(err) => yield Err(err),
+                }
+            }
+        })
+    }
+
+    async fn get_issue_comment(&self, issue_id : u32, comment_id : u32) -> Result<Comment, Self::Error> {
+        let _flock = AsyncFileLock::shared(&self.lock_file).await?;
+        let db = self.inner.read().await;
+        let mut path = db.path.join(issue_id.to_string());
+        drop(db);
+        path.push(comment_id.to_string());
+
+        let mut file = tokio::fs::File::open(&path).await?;
+        let fs_meta = file.metadata().await?;
+
+        let mut buf = String::new();
+        file.read_to_string(&mut buf).await?;
+
+        let (created, modified) = {
+            let path = path.clone();
+            match blocking(move || read_sync_meta(&path)).await? {
+                Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+                None => (fs_meta.created()?, fs_meta.modified()?),
+            }
+        };
+
+        Ok(Comment{
+            issue_id,
+            comment_id,
+            created,
+            modified,
+            content : buf,
+        })
+    }
+
+    async fn search(&self, query : &str, any : bool) -> Result<Vec<SearchHit>, Self::Error> {
+        let tokens = tokenize(query);
+
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (scores, matched) = {
+            let _flock = AsyncFileLock::shared(&self.lock_file).await?;
+            let db = self.inner.read().await;
+
+            let mut scores : HashMap<(u32, u32), u32> = HashMap::new();
+            let mut doc_sets : Vec<std::collections::HashSet<(u32, u32)>> = Vec::new();
+
+            for token in &tokens {
+                let mut set = std::collections::HashSet::new();
+
+                if let Some(postings) = db.search_index.get(token) {
+                    for p in postings {
+                        let key = (p.issue_id, p.comment_id);
+                        *scores.entry(key).or_insert(0) += p.tf;
+                        set.insert(key);
+                    }
+                }
+
+                doc_sets.push(set);
+            }
+
+            let matched : Vec<(u32, u32)> = if any {
+                scores.keys().copied().collect()
+            } else {
+                let mut it = doc_sets.into_iter();
+                let mut acc = it.next().unwrap_or_default();
+
+                for set in it {
+                    acc = acc.intersection(&set).copied().collect();
+                }
+
+                acc.into_iter().collect()
+            };
+
+            (scores, matched)
+        };
+
+        let mut hits : Vec<(SearchHit, SystemTime)> = Vec::with_capacity(matched.len());
+
+        for (issue_id, comment_id) in matched {
+            let modified = self.get_issue_comment(issue_id, comment_id).await
+                .map(|c| c.modified)
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+
+            let hit = SearchHit{
+                issue_id,
+                comment_id,
+                score : scores[&(issue_id, comment_id)],
+            };
+
+            hits.push((hit, modified));
+        }
+
+        hits.sort_by(|a, b| {
+            b.0.score.cmp(&a.0.score).then_with(|| b.1.cmp(&a.1))
+        });
+
+        Ok(hits.into_iter().map(|(hit, _)| hit).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a fresh `.praiadb` directory under the system temp dir, removed
+    /// on drop so repeated test runs don't pile up state
+    struct TempDb {
+        path : PathBuf,
+    }
+
+    impl TempDb {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("praia-async-test-{}", rand::random::<u64>()));
+            std::fs::create_dir(&path).unwrap();
+            Self{path}
+        }
+
+        async fn open(&self) -> AsyncFsDb {
+            AsyncFsDb::new(self.path.clone(), LockMode::ReadWrite).await.unwrap()
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[tokio::test]
+    async fn new_issue_and_comment_are_searchable() {
+        let tmp = TempDb::new();
+        let db = tmp.open().await;
+
+        let issue_id = db.new_issue("an async issue").await.unwrap_or_else(|e| panic!("{e}"));
+        db.new_comment(issue_id, "an async reply").await.unwrap_or_else(|e| panic!("{e}"));
+
+        let hits = db.search("async", false).await.unwrap();
+        assert_eq!(hits.len(), 2);
+    }
+
+    /// reproduces the multi-writer race the sync `FsDb` was fixed for:
+    /// several concurrent `new_comment` calls on the same issue must
+    /// all land (distinct ids, no `AlreadyExists` panics), and every
+    /// comment's postings must survive in `search_index`, not just the
+    /// last writer's.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_new_comment_persists_every_writer() {
+        let tmp = TempDb::new();
+        let db = std::sync::Arc::new(tmp.open().await);
+
+        let issue_id = db.new_issue("an issue").await.unwrap_or_else(|e| panic!("{e}"));
+
+        let writers = (0..8).map(|i| {
+            let db = db.clone();
+            tokio::spawn(async move {
+                db.new_comment(issue_id, &format!("reply from writer {i}")).await
+            })
+        });
+
+        let mut ids : Vec<u32> = Vec::new();
+        for w in writers {
+            ids.push(w.await.unwrap().unwrap());
+        }
+
+        ids.sort();
+        assert_eq!(ids, (1..9).collect::<Vec<u32>>());
+
+        let hits = db.search("reply", true).await.unwrap();
+        assert_eq!(hits.len(), 8, "every writer's comment must stay searchable");
+    }
+}
@@ -7,37 +7,177 @@ use std::sync::RwLock;
 use std::collections::HashMap;
 
 use quick_from::QuickFrom;
+use fs2::FileExt;
+use serde::{Serialize, Deserialize};
+
+/// serialize a `SystemTime` as an RFC3339 string, for `Issue`/`Comment`'s
+/// `Serialize` impl; only a `serialize_with`, since nothing deserializes
+/// these types back
+mod rfc3339 {
+    use std::time::SystemTime;
+    use serde::Serializer;
+
+    pub fn serialize<S : Serializer>(t : &SystemTime, s : S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&chrono::DateTime::<chrono::Utc>::from(*t).to_rfc3339())
+    }
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Comment {
     pub issue_id : u32,
     pub comment_id : u32,
+    #[serde(serialize_with = "rfc3339::serialize")]
     pub created : SystemTime,
+    #[serde(serialize_with = "rfc3339::serialize")]
     pub modified : SystemTime,
     pub content : String,
 }
 
-#[derive(Debug, Clone)]
+/// open/closed lifecycle state of an issue; issues with no `meta.toml`
+/// default to `Open`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueStatus {
+    #[default]
+    Open,
+    Closed,
+}
+
+impl std::str::FromStr for IssueStatus {
+    type Err = FsError;
+
+    fn from_str(s : &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(IssueStatus::Open),
+            "closed" => Ok(IssueStatus::Closed),
+            _ => Err(FsError::BadStatus),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Issue {
     pub issue_id : u32,
+    #[serde(serialize_with = "rfc3339::serialize")]
     pub created : SystemTime,
+    #[serde(serialize_with = "rfc3339::serialize")]
     pub modified : SystemTime,
+    pub status : IssueStatus,
+    pub labels : Vec<String>,
+    pub assignee : Option<String>,
     /// the first comment
     pub content : String,
 }
 
+/// an issue's metadata, persisted to `<issue_id>/meta.toml` as a sidecar
+/// next to its (untouched) comment files. missing entirely for issues
+/// predating this feature, in which case it's taken to be the default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct IssueMeta {
+    #[serde(default)]
+    pub(crate) status : IssueStatus,
+    #[serde(default)]
+    pub(crate) labels : Vec<String>,
+    #[serde(default)]
+    pub(crate) assignee : Option<String>,
+}
+
+pub(crate) fn meta_path(issue_path : &Path) -> PathBuf {
+    issue_path.join("meta.toml")
+}
+
+pub(crate) fn read_issue_meta(issue_path : &Path) -> Result<IssueMeta, FsError> {
+    let text = match std::fs::read_to_string(meta_path(issue_path)) {
+        Ok(text) => text,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(IssueMeta::default()),
+        Err(err) => return Err(err.into()),
+    };
+
+    toml::from_str(&text).map_err(|_| FsError::BadDb)
+}
+
+pub(crate) fn write_issue_meta(issue_path : &Path, meta : &IssueMeta) -> Result<(), FsError> {
+    let text = toml::to_string(meta).map_err(|_| FsError::BadDb)?;
+    std::fs::write(meta_path(issue_path), text)?;
+    Ok(())
+}
+
+/// criteria an issue must satisfy to be returned by `get_issues`; an
+/// unset field imposes no constraint. the default filter matches every
+/// issue.
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilter {
+    pub status : Option<IssueStatus>,
+    pub label : Option<String>,
+    pub assignee : Option<String>,
+}
+
+impl IssueFilter {
+    pub(crate) fn matches(&self, meta : &IssueMeta) -> bool {
+        if let Some(status) = self.status {
+            if meta.status != status {
+                return false;
+            }
+        }
+
+        if let Some(label) = &self.label {
+            if !meta.labels.iter().any(|l| l == label) {
+                return false;
+            }
+        }
+
+        if let Some(assignee) = &self.assignee {
+            if meta.assignee.as_deref() != Some(assignee.as_str()) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 pub type BoxIter<'a, T> = Box<dyn Iterator<Item = T> + 'a>;
 
+/// a single search result, identifying a comment (or, when `comment_id`
+/// is 0, an issue's first comment) and its relevance score
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub issue_id : u32,
+    pub comment_id : u32,
+    pub score : u32,
+}
+
 pub trait Db {
     type Error : std::error::Error;
 
     fn new_issue(&self, first_comment : &str) -> Result<u32, Self::Error>;
     fn new_comment(&self, issue_id : u32, content : &str) -> Result<u32, Self::Error>;
 
-    fn get_issues<'a>(&'a self) -> BoxIter<'a, Result<Issue, Self::Error>>;
+    /// list issues matching `filter`; pass `&IssueFilter::default()` for
+    /// every issue
+    fn get_issues<'a>(&'a self, filter : &IssueFilter) -> BoxIter<'a, Result<Issue, Self::Error>>;
     fn get_issue(&self, issue_id : u32) -> Result<Issue, Self::Error>;
     fn get_issue_comments(&self, issue_id : u32) -> BoxIter<Result<Comment, Self::Error>>;
     fn get_issue_comment(&self, issue_id : u32, comment_id : u32) -> Result<Comment, Self::Error>;
+
+    fn set_status(&self, issue_id : u32, status : IssueStatus) -> Result<(), Self::Error>;
+    fn add_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error>;
+    fn remove_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error>;
+    fn assign(&self, issue_id : u32, assignee : Option<String>) -> Result<(), Self::Error>;
+
+    /// tokenize `query` and rank matching issues/comments by summed term
+    /// frequency, ties broken by most recently modified first. `any`
+    /// selects union (OR) semantics instead of the default intersection
+    /// (AND) across query tokens.
+    fn search(&self, query : &str, any : bool) -> Result<Vec<SearchHit>, Self::Error>;
+}
+
+/// lowercase `s` and split it on non-alphanumeric boundaries
+pub(crate) fn tokenize(s : &str) -> Vec<String> {
+    s.split(|c : char| !c.is_alphanumeric())
+        .filter(|tok| !tok.is_empty())
+        .map(|tok| tok.to_lowercase())
+        .collect()
 }
 
 
@@ -45,9 +185,12 @@ pub trait Db {
 pub enum FsError {
     BadIndex,
     BadDb,
+    BadStatus,
 
     NoIssue(u32),
 
+    ReadOnly,
+
     #[quick_from]
     Io(std::io::Error),
 }
@@ -61,33 +204,205 @@ impl std::fmt::Display for FsError {
         match self {
             BadIndex => write!(f, "index corrupted"),
             BadDb => write!(f, "db corrupted"),
+            BadStatus => write!(f, "invalid issue status"),
             NoIssue(id) => write!(f, "issue {id} not found"),
+            ReadOnly => write!(f, "db opened read-only"),
             Io(err) => std::fmt::Display::fmt(err, f),
         }
     }
 }
 
-// TODO: implement file lock
-pub struct FsDb(RwLock<FsDbInner>);
+/// whether a `FsDb` may take the exclusive lock needed to mutate the
+/// directory. a process that only ever reads (e.g. `list`, `search`)
+/// should open with `ReadOnly` so it never contends with writers for
+/// the exclusive lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    ReadOnly,
+    ReadWrite,
+}
 
-struct FsDbInner {
-    path : Box<Path>,
-    issue_count : u32,
-    comment_count : HashMap<u32, u32>,
+/// an RAII guard over an OS advisory lock on `index.lock`, released on
+/// drop so early returns (via `?`) can't leak it
+struct FileLock<'a> {
+    file : &'a File,
+}
+
+impl<'a> FileLock<'a> {
+    fn shared(file : &'a File) -> Result<Self, FsError> {
+        file.lock_shared()?;
+        Ok(Self{file})
+    }
+
+    fn exclusive(file : &'a File) -> Result<Self, FsError> {
+        file.lock_exclusive()?;
+        Ok(Self{file})
+    }
+}
+
+impl<'a> Drop for FileLock<'a> {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// a posting in the inverted index: a token occurred `tf` times in the
+/// given comment (`comment_id` 0 is an issue's first comment)
+#[derive(Debug, Clone)]
+pub(crate) struct Posting {
+    pub(crate) issue_id : u32,
+    pub(crate) comment_id : u32,
+    pub(crate) tf : u32,
+}
+
+/// a comment's stable identity, independent of its (per-clone) sequential
+/// id, so two clones of a db can recognize the same comment after a
+/// pull re-numbers it. persisted in a `<comment_id>.meta` sidecar next
+/// to the comment file.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SyncMeta {
+    pub(crate) hash : u64,
+    pub(crate) origin_id : u64,
+    pub(crate) created : SystemTime,
+    pub(crate) modified : SystemTime,
+}
+
+/// hash `content`, `created`, and `origin_id` together into a stable
+/// content identity for a comment, via FNV-1a rather than
+/// `std::hash::Hash`/`DefaultHasher`: this hash is persisted in
+/// `.meta` sidecars and exchanged between clones during sync, so it
+/// has to stay stable across Rust versions, not just within one run.
+pub(crate) fn content_hash(content : &str, created : SystemTime, origin_id : u64) -> u64 {
+    const FNV_OFFSET : u64 = 0xcbf29ce484222325;
+    const FNV_PRIME : u64 = 0x100000001b3;
+
+    let mut hash = FNV_OFFSET;
+
+    let mut fold = |bytes : &[u8]| {
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    fold(content.as_bytes());
+
+    let created_nanos = created.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    fold(&created_nanos.to_le_bytes());
+    fold(&origin_id.to_le_bytes());
+
+    hash
+}
+
+pub(crate) fn sync_meta_path(content_path : &Path) -> PathBuf {
+    let mut name = content_path.file_name().expect("comment path has a file name").to_os_string();
+    name.push(".meta");
+    content_path.with_file_name(name)
+}
+
+pub(crate) fn read_sync_meta(content_path : &Path) -> Result<Option<SyncMeta>, FsError> {
+    let text = match std::fs::read_to_string(sync_meta_path(content_path)) {
+        Ok(text) => text,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut it = text.split_whitespace();
+
+    let hash : u64 = it.next().ok_or(FsError::BadDb)?.parse().map_err(|_| FsError::BadDb)?;
+    let origin_id : u64 = it.next().ok_or(FsError::BadDb)?.parse().map_err(|_| FsError::BadDb)?;
+    let created_nanos : u64 = it.next().ok_or(FsError::BadDb)?.parse().map_err(|_| FsError::BadDb)?;
+    let modified_nanos : u64 = it.next().ok_or(FsError::BadDb)?.parse().map_err(|_| FsError::BadDb)?;
+
+    Ok(Some(SyncMeta{
+        hash,
+        origin_id,
+        created : SystemTime::UNIX_EPOCH + std::time::Duration::from_nanos(created_nanos),
+        modified : SystemTime::UNIX_EPOCH + std::time::Duration::from_nanos(modified_nanos),
+    }))
+}
+
+pub(crate) fn write_sync_meta(content_path : &Path, meta : &SyncMeta) -> Result<(), FsError> {
+    let as_nanos = |t : SystemTime| t.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+
+    std::fs::write(
+        sync_meta_path(content_path),
+        format!("{} {} {} {}\n", meta.hash, meta.origin_id, as_nanos(meta.created), as_nanos(meta.modified)),
+    )?;
+
+    Ok(())
+}
+
+/// the stable hash of the comment at `content_path`, computed from its
+/// sidecar if one exists, or derived fresh (and not persisted) if not
+fn comment_hash(content_path : &Path, origin_id : u64) -> Result<u64, FsError> {
+    if let Some(meta) = read_sync_meta(content_path)? {
+        return Ok(meta.hash);
+    }
+
+    let content = std::fs::read_to_string(content_path)?;
+    let created = content_path.metadata()?.created()?;
+
+    Ok(content_hash(&content, created, origin_id))
+}
+
+/// load this replica's origin id from the `origin` file, generating a
+/// fresh random one the first time a db is opened. only persisted in
+/// `ReadWrite` mode; a `ReadOnly` open (e.g. `list`, `search`, or
+/// `FsTransport::fetch`) derives the same id for this process's use but
+/// leaves the directory untouched.
+pub(crate) fn read_or_create_origin_id(path : &Path, mode : LockMode) -> Result<u64, FsError> {
+    let origin_path = path.join("origin");
+
+    match std::fs::read_to_string(&origin_path) {
+        Ok(text) => text.trim().parse().map_err(|_| FsError::BadDb),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            let origin_id = rand::random::<u64>();
+            if mode == LockMode::ReadWrite {
+                std::fs::write(&origin_path, format!("{origin_id}\n"))?;
+            }
+            Ok(origin_id)
+        },
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub struct FsDb {
+    inner : RwLock<FsDbInner>,
+    lock_file : File,
+    mode : LockMode,
+}
+
+pub(crate) struct FsDbInner {
+    pub(crate) path : Box<Path>,
+    pub(crate) issue_count : u32,
+    pub(crate) comment_count : HashMap<u32, u32>,
+    pub(crate) search_index : HashMap<String, Vec<Posting>>,
+    pub(crate) origin_id : u64,
 }
 
 impl FsDbInner {
-    fn create_index(path : Box<Path>) -> Result<Self, FsError> {
-        let index_path = path.join("index.txt");
+    /// scan `path` from scratch, backfilling `search_index` and (in
+    /// `ReadWrite` mode only) the `origin` file, missing `.meta`
+    /// sidecars, `index.txt`, and `search.idx`. a `ReadOnly` open
+    /// derives the same in-memory index without writing anything, so it
+    /// can't fail on a read-only mount or race a concurrent writer.
+    fn create_index(path : Box<Path>, mode : LockMode) -> Result<Self, FsError> {
+        let origin_id = read_or_create_origin_id(&path, mode)?;
 
         let mut issue_count = 0;
         let mut comment_count = HashMap::new();
+        let mut search_index = HashMap::new();
 
         for issue_res in read_dir(&path)? {
             let issue = issue_res?;
 
             let name = issue.file_name();
-            if name == "index.txt" {
+            if name == "index.txt" || name == "search.idx" || name == "index.lock" || name == "origin" {
                 continue
             }
 
@@ -102,105 +417,545 @@ impl FsDbInner {
             for comment_res in read_dir(issue.path())? {
                 let comment = comment_res?;
 
-                let comment_id : u32 = comment.file_name()
-                    .to_str().ok_or(FsError::BadDb)?
-                    .parse().map_err(|_| FsError::BadDb)?;
+                let name = comment.file_name();
+                let name = name.to_str().ok_or(FsError::BadDb)?;
+                if name.ends_with(".meta") || name == "meta.toml" {
+                    continue
+                }
+
+                let comment_id : u32 = name.parse().map_err(|_| FsError::BadDb)?;
 
                 max_comment = max_comment.max(comment_id+1);
+
+                let content = std::fs::read_to_string(comment.path())?;
+                index_content(&mut search_index, issue_id, comment_id, &content);
+
+                if mode == LockMode::ReadWrite && read_sync_meta(&comment.path())?.is_none() {
+                    let meta = comment.metadata()?;
+                    let created = meta.created()?;
+                    let modified = meta.modified()?;
+
+                    write_sync_meta(&comment.path(), &SyncMeta{
+                        hash : content_hash(&content, created, origin_id),
+                        origin_id,
+                        created,
+                        modified,
+                    })?;
+                }
             }
 
             comment_count.insert(issue_id, max_comment);
         }
 
-        let ret = Self{path, issue_count, comment_count};
+        let ret = Self{path, issue_count, comment_count, search_index, origin_id};
 
-        ret.save_index()?;
+        if mode == LockMode::ReadWrite {
+            ret.save_index()?;
+        }
 
         Ok(ret)
     }
 
-    fn read_index(path : Box<Path>) -> Result<Self, FsError> {
+    fn read_index(path : Box<Path>, mode : LockMode) -> Result<Self, FsError> {
+        let (issue_count, comment_count) = read_counts(&path.join("index.txt"))?;
+        let search_index = read_search_index(&path)?;
+        let origin_id = read_or_create_origin_id(&path, mode)?;
 
-        let index_path = path.join("index.txt");
+        Ok(Self{path, issue_count, comment_count, search_index, origin_id})
+    }
 
-        let mut index = BufReader::new(File::open(&index_path)?);
+    fn save_index(&self) -> Result<(), FsError> {
+        write_index(&self.path, self.issue_count, &self.comment_count, &self.search_index)
+    }
 
-        let mut buf = String::new();
+    /// tokenize `content` and fold it into the in-memory inverted index;
+    /// callers still need `save_index` to persist the result
+    fn index_comment(&mut self, issue_id : u32, comment_id : u32, content : &str) {
+        index_content(&mut self.search_index, issue_id, comment_id, content);
+    }
 
-        let issue_count = if index.read_line(&mut buf)? > 0 {
-            buf.trim_end().parse().map_err(|_| FsError::BadIndex)?
-        } else {
+    /// re-read `issue_count`/`comment_count`/`search_index` from disk,
+    /// picking up writes made by other processes since this `FsDbInner`
+    /// was loaded. called after taking the exclusive lock and before
+    /// allocating a new id or folding a new comment into the index, so
+    /// a concurrent writer's postings aren't clobbered by a stale
+    /// in-memory index on the next `save_index`.
+    fn refresh_counts(&mut self) -> Result<(), FsError> {
+        let (issue_count, comment_count) = read_counts(&self.path.join("index.txt"))?;
+        self.issue_count = issue_count;
+        self.comment_count = comment_count;
+        self.search_index = read_search_index(&self.path)?;
+        Ok(())
+    }
+}
+
+/// write `issue_count`/`comment_count`/`search_index` to `index.txt` and
+/// `search.idx`, under `path`; shared by the sync and async `FsDb`
+/// implementations so the on-disk format only has one writer
+pub(crate) fn write_index(
+    path : &Path,
+    issue_count : u32,
+    comment_count : &HashMap<u32, u32>,
+    search_index : &HashMap<String, Vec<Posting>>,
+) -> Result<(), FsError> {
+    let index_path = path.join("index.txt");
+
+    let mut index = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(index_path)?;
+
+    writeln!(index, "{issue_count}")?;
+
+    for (k, v) in comment_count.iter() {
+        writeln!(index, "{k} {v}")?;
+    }
+
+    save_search_index(path, search_index)?;
+
+    Ok(())
+}
+
+/// parse the `issue_count`/`comment_count` portion of `index.txt`
+pub(crate) fn read_counts(index_path : &Path) -> Result<(u32, HashMap<u32, u32>), FsError> {
+    let mut index = BufReader::new(File::open(index_path)?);
+
+    let mut buf = String::new();
+
+    let issue_count = if index.read_line(&mut buf)? > 0 {
+        buf.trim_end().parse().map_err(|_| FsError::BadIndex)?
+    } else {
+        return Err(FsError::BadIndex);
+    };
+
+    buf.clear();
+
+    let mut comment_count = HashMap::new();
+
+    while index.read_line(&mut buf)? > 0 {
+
+        let mut it = buf.split(" ");
+
+        let k : u32 = it.next()
+            .ok_or(FsError::BadIndex)?
+            .parse()
+            .map_err(|_| FsError::BadIndex)?;
+
+        let v : u32 = it.next()
+            .ok_or(FsError::BadIndex)?
+            .trim_end()
+            .parse()
+            .map_err(|_| FsError::BadIndex)?;
+
+        if it.next().is_some() {
             return Err(FsError::BadIndex);
-        };
+        }
+
+        comment_count.insert(k, v);
 
         buf.clear();
+    }
 
-        let mut comment_count = HashMap::new();
+    Ok((issue_count, comment_count))
+}
+
+/// fold the tokens of `content` into `search_index` as postings for
+/// `(issue_id, comment_id)`
+pub(crate) fn index_content(
+    search_index : &mut HashMap<String, Vec<Posting>>,
+    issue_id : u32,
+    comment_id : u32,
+    content : &str,
+) {
+    let mut term_freq : HashMap<String, u32> = HashMap::new();
+
+    for token in tokenize(content) {
+        *term_freq.entry(token).or_insert(0) += 1;
+    }
+
+    for (token, tf) in term_freq {
+        search_index.entry(token)
+            .or_default()
+            .push(Posting{issue_id, comment_id, tf});
+    }
+}
 
-        while index.read_line(&mut buf)? > 0 {
+/// read `search.idx` next to `index.txt`; a missing file just means an
+/// empty index (e.g. a db predating full-text search)
+pub(crate) fn read_search_index(path : &Path) -> Result<HashMap<String, Vec<Posting>>, FsError> {
+    let idx_path = path.join("search.idx");
 
-            let mut it = buf.split(" ");
+    let file = match File::open(&idx_path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(err) => return Err(err.into()),
+    };
 
-            let k : u32 = it.next()
+    let mut reader = BufReader::new(file);
+    let mut search_index = HashMap::new();
+    let mut buf = String::new();
+
+    while reader.read_line(&mut buf)? > 0 {
+        let mut it = buf.trim_end().split(' ');
+
+        let token = it.next().ok_or(FsError::BadIndex)?.to_string();
+        let count : u32 = it.next()
+            .ok_or(FsError::BadIndex)?
+            .parse()
+            .map_err(|_| FsError::BadIndex)?;
+
+        let mut postings = Vec::with_capacity(count as usize);
+
+        for _ in 0..count {
+            buf.clear();
+            if reader.read_line(&mut buf)? == 0 {
+                return Err(FsError::BadIndex);
+            }
+
+            let mut fields = buf.trim_end().split(' ');
+
+            let issue_id : u32 = fields.next()
                 .ok_or(FsError::BadIndex)?
                 .parse()
                 .map_err(|_| FsError::BadIndex)?;
-
-            let v : u32 = it.next()
+            let comment_id : u32 = fields.next()
+                .ok_or(FsError::BadIndex)?
+                .parse()
+                .map_err(|_| FsError::BadIndex)?;
+            let tf : u32 = fields.next()
                 .ok_or(FsError::BadIndex)?
-                .trim_end()
                 .parse()
                 .map_err(|_| FsError::BadIndex)?;
 
-            if it.next().is_some() {
-                return Err(FsError::BadIndex);
-            }
-
-            comment_count.insert(k, v);
-
-            buf.clear();
+            postings.push(Posting{issue_id, comment_id, tf});
         }
 
-        Ok(Self{path, issue_count, comment_count})
+        search_index.insert(token, postings);
+
+        buf.clear();
     }
 
-    fn save_index(&self) -> Result<(), FsError> {
+    Ok(search_index)
+}
 
-        let index_path = self.path.join("index.txt");
+fn save_search_index(path : &Path, search_index : &HashMap<String, Vec<Posting>>) -> Result<(), FsError> {
+    let idx_path = path.join("search.idx");
 
-        let mut index = OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(index_path)?;
+    let mut index = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(idx_path)?;
 
-        writeln!(index, "{}", self.issue_count)?;
+    for (token, postings) in search_index.iter() {
+        writeln!(index, "{token} {}", postings.len())?;
 
-        for (k, v) in self.comment_count.iter() {
-            writeln!(index, "{k} {v}")?;
+        for p in postings {
+            writeln!(index, "{} {} {}", p.issue_id, p.comment_id, p.tf)?;
         }
-
-        Ok(())
     }
+
+    Ok(())
 }
 
 impl FsDb {
-    pub fn new(path : PathBuf) -> Result<Self, FsError> {
+    pub fn new(path : PathBuf, mode : LockMode) -> Result<Self, FsError> {
         let path = path.into_boxed_path();
+
+        let lock_file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.join("index.lock"))?;
+
+        // a db that already has an index.txt is just being read, so take
+        // the shared lock and never contend with another process's
+        // exclusive section; only a first-time scan, which has to write
+        // index.txt/search.idx/origin, needs the exclusive lock -- and
+        // only when `mode` actually permits writing them.
         let inner = if path.join("index.txt").exists() {
-            FsDbInner::read_index(path)?
+            let _flock = FileLock::shared(&lock_file)?;
+            FsDbInner::read_index(path, mode)?
         } else {
-            FsDbInner::create_index(path)?
+            let _flock = FileLock::exclusive(&lock_file)?;
+
+            // re-check: another process may have finished its own
+            // first-time scan while we waited for the exclusive lock
+            if path.join("index.txt").exists() {
+                FsDbInner::read_index(path, mode)?
+            } else {
+                FsDbInner::create_index(path, mode)?
+            }
         };
 
-        Ok(Self(RwLock::new(inner)))
+        Ok(Self{inner : RwLock::new(inner), lock_file, mode})
     }
 
     pub fn save_index(&self) -> Result<(), FsError> {
-        self.0.write().unwrap().save_index()
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+        self.inner.write().unwrap().save_index()
+    }
+
+    /// snapshot every issue and comment, keyed by stable hash rather
+    /// than local sequential id, for exchange with another clone
+    pub fn export_records(&self) -> Result<Vec<SyncRecord>, FsError> {
+        let _flock = FileLock::shared(&self.lock_file)?;
+        let db = self.inner.read().unwrap();
+
+        let mut records = Vec::new();
+
+        for issue_id in 0..db.issue_count {
+            let mut path = db.path.join(issue_id.to_string());
+            path.push("0");
+
+            if !path.exists() {
+                continue
+            }
+
+            let content = std::fs::read_to_string(&path)?;
+            let meta = sync_meta_or_fallback(&path, &content, db.origin_id)?;
+            let issue_hash = meta.hash;
+
+            records.push(SyncRecord{
+                issue_hash,
+                comment_id : 0,
+                hash : meta.hash,
+                origin_id : meta.origin_id,
+                created : meta.created,
+                modified : meta.modified,
+                content,
+            });
+
+            let count = *db.comment_count.get(&issue_id).unwrap_or(&0);
+
+            for comment_id in 1..count {
+                let mut path = db.path.join(issue_id.to_string());
+                path.push(comment_id.to_string());
+
+                if !path.exists() {
+                    continue
+                }
+
+                let content = std::fs::read_to_string(&path)?;
+                let meta = sync_meta_or_fallback(&path, &content, db.origin_id)?;
+
+                records.push(SyncRecord{
+                    issue_hash,
+                    comment_id,
+                    hash : meta.hash,
+                    origin_id : meta.origin_id,
+                    created : meta.created,
+                    modified : meta.modified,
+                    content,
+                });
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// merge `records` into this db: an issue or comment whose hash is
+    /// already present is skipped, genuinely new ones are appended
+    /// under freshly allocated local ids, preserving the remote
+    /// `created`/`modified` rather than stamping local mtimes. comments
+    /// whose parent issue isn't present (yet) are skipped as orphans.
+    pub fn import_records(&self, records : &[SyncRecord]) -> Result<(), FsError> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+
+        let mut db = self.inner.write().unwrap();
+        db.refresh_counts()?;
+
+        let mut issue_ids = local_issue_hash_map(&db)?;
+        let mut changed = false;
+
+        for rec in records.iter().filter(|r| r.comment_id == 0) {
+            if issue_ids.contains_key(&rec.hash) {
+                continue
+            }
+
+            let issue_id = db.issue_count;
+            let path = db.path.join(issue_id.to_string());
+
+            create_dir(&path)?;
+            db.comment_count.insert(issue_id, 1);
+            db.issue_count += 1;
+            db.index_comment(issue_id, 0, &rec.content);
+
+            let path = path.join("0");
+            std::fs::write(&path, &rec.content)?;
+            write_sync_meta(&path, &SyncMeta{
+                hash : rec.hash,
+                origin_id : rec.origin_id,
+                created : rec.created,
+                modified : rec.modified,
+            })?;
+
+            issue_ids.insert(rec.hash, issue_id);
+            changed = true;
+        }
+
+        for rec in records.iter().filter(|r| r.comment_id != 0) {
+            let issue_id = match issue_ids.get(&rec.issue_hash) {
+                Some(issue_id) => *issue_id,
+                None => continue,
+            };
+
+            if issue_comment_hashes(&db, issue_id)?.contains(&rec.hash) {
+                continue
+            }
+
+            let comment_id = *db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+            let mut path = db.path.join(issue_id.to_string());
+            path.push(comment_id.to_string());
+
+            std::fs::write(&path, &rec.content)?;
+            write_sync_meta(&path, &SyncMeta{
+                hash : rec.hash,
+                origin_id : rec.origin_id,
+                created : rec.created,
+                modified : rec.modified,
+            })?;
+
+            db.index_comment(issue_id, comment_id, &rec.content);
+            *db.comment_count.get_mut(&issue_id).unwrap() += 1;
+            changed = true;
+        }
+
+        if changed {
+            db.save_index()?;
+        }
+
+        Ok(())
+    }
+
+    /// pull remote records from `upstream` and merge them in
+    pub fn pull(&self, upstream : &dyn SyncTransport) -> Result<(), FsError> {
+        self.import_records(&upstream.fetch()?)
+    }
+
+    /// push this db's records to `upstream`
+    pub fn push(&self, upstream : &dyn SyncTransport) -> Result<(), FsError> {
+        upstream.send(&self.export_records()?)
+    }
+
+    /// pull, then push, so both sides end up with the union of records
+    pub fn sync(&self, upstream : &dyn SyncTransport) -> Result<(), FsError> {
+        self.pull(upstream)?;
+        self.push(upstream)
+    }
+}
+
+fn sync_meta_or_fallback(content_path : &Path, content : &str, origin_id : u64) -> Result<SyncMeta, FsError> {
+    if let Some(meta) = read_sync_meta(content_path)? {
+        return Ok(meta);
+    }
+
+    let fs_meta = content_path.metadata()?;
+    let created = fs_meta.created()?;
+    let modified = fs_meta.modified()?;
+
+    Ok(SyncMeta{
+        hash : content_hash(content, created, origin_id),
+        origin_id,
+        created,
+        modified,
+    })
+}
+
+/// map each locally-known issue's stable hash to its local sequential id
+fn local_issue_hash_map(db : &FsDbInner) -> Result<HashMap<u64, u32>, FsError> {
+    let mut map = HashMap::new();
+
+    for issue_id in 0..db.issue_count {
+        let mut path = db.path.join(issue_id.to_string());
+        path.push("0");
+
+        if !path.exists() {
+            continue
+        }
+
+        map.insert(comment_hash(&path, db.origin_id)?, issue_id);
+    }
+
+    Ok(map)
+}
+
+/// the set of stable hashes of every comment already stored under `issue_id`
+fn issue_comment_hashes(db : &FsDbInner, issue_id : u32) -> Result<std::collections::HashSet<u64>, FsError> {
+    let count = match db.comment_count.get(&issue_id) {
+        Some(n) => *n,
+        None => return Ok(Default::default()),
+    };
+
+    let mut hashes = std::collections::HashSet::new();
+
+    for comment_id in 0..count {
+        let mut path = db.path.join(issue_id.to_string());
+        path.push(comment_id.to_string());
+
+        if !path.exists() {
+            continue
+        }
+
+        hashes.insert(comment_hash(&path, db.origin_id)?);
     }
 
+    Ok(hashes)
+}
 
+/// one issue or comment, identified by its stable hash (and, for
+/// comments, its parent issue's stable hash) rather than a local
+/// sequential id — the unit of exchange between clones during sync
+#[derive(Debug, Clone)]
+pub struct SyncRecord {
+    pub issue_hash : u64,
+    /// 0 for an issue's first comment, matching `Comment::comment_id`
+    pub comment_id : u32,
+    pub hash : u64,
+    pub origin_id : u64,
+    pub created : SystemTime,
+    pub modified : SystemTime,
+    pub content : String,
+}
+
+/// where a db exchanges `SyncRecord`s with during push/pull. starts with
+/// a filesystem upstream (`FsTransport`); an HTTP or git remote can
+/// implement this trait later without touching the merge logic above.
+pub trait SyncTransport {
+    fn fetch(&self) -> Result<Vec<SyncRecord>, FsError>;
+    fn send(&self, records : &[SyncRecord]) -> Result<(), FsError>;
+}
+
+/// a filesystem-backed upstream: another `.praiadb` directory, reachable
+/// by path (e.g. a mounted drive, or a directory kept in sync out of
+/// band by rsync)
+pub struct FsTransport {
+    path : PathBuf,
+}
+
+impl FsTransport {
+    pub fn new(path : PathBuf) -> Self {
+        Self{path}
+    }
+}
+
+impl SyncTransport for FsTransport {
+    fn fetch(&self) -> Result<Vec<SyncRecord>, FsError> {
+        FsDb::new(self.path.clone(), LockMode::ReadOnly)?.export_records()
+    }
+
+    fn send(&self, records : &[SyncRecord]) -> Result<(), FsError> {
+        FsDb::new(self.path.clone(), LockMode::ReadWrite)?.import_records(records)
+    }
 }
 
 
@@ -209,7 +964,15 @@ impl Db for FsDb {
     type Error = FsError;
 
     fn new_issue(&self, first_comment : &str) -> Result<u32, Self::Error> {
-        let mut db = self.0.write().unwrap();
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+
+        let mut db = self.inner.write().unwrap();
+        db.refresh_counts()?;
+
         let issue_id = db.issue_count;
 
         let mut path = db.path.join(issue_id.to_string());
@@ -218,18 +981,41 @@ impl Db for FsDb {
 
         db.comment_count.insert(issue_id, 1);
         db.issue_count += 1;
+        db.index_comment(issue_id, 0, first_comment);
 
         path.push("0");
 
-        let mut file = OpenOptions::new().create_new(true).write(true).open(path)?;
+        let mut file = OpenOptions::new().create_new(true).write(true).open(&path)?;
         file.write_all(first_comment.as_bytes())?;
 
+        let now = SystemTime::now();
+        write_sync_meta(&path, &SyncMeta{
+            hash : content_hash(first_comment, now, db.origin_id),
+            origin_id : db.origin_id,
+            created : now,
+            modified : now,
+        })?;
+
+        // persist while the exclusive lock is still held, so the id
+        // allocation and the index.txt/search.idx write are one atomic
+        // section; a second writer's refresh_counts() can then never
+        // observe the new file on disk without also seeing its id
+        // reflected in index.txt
+        db.save_index()?;
+
         Ok(issue_id)
     }
 
 
     fn new_comment(&self, issue_id : u32, content : &str) -> Result<u32, Self::Error> {
-        let mut db = self.0.write().unwrap();
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+
+        let mut db = self.inner.write().unwrap();
+        db.refresh_counts()?;
 
         let mut path = db.path.join(issue_id.to_string());
 
@@ -238,25 +1024,116 @@ impl Db for FsDb {
 
         path.push(comment_id.to_string());
 
-        let mut file = OpenOptions::new().create_new(true).write(true).open(path)?;
+        let mut file = OpenOptions::new().create_new(true).write(true).open(&path)?;
         file.write_all(content.as_bytes())?;
 
         *ent = *ent + 1;
+        db.index_comment(issue_id, comment_id, content);
+
+        let now = SystemTime::now();
+        write_sync_meta(&path, &SyncMeta{
+            hash : content_hash(content, now, db.origin_id),
+            origin_id : db.origin_id,
+            created : now,
+            modified : now,
+        })?;
+
+        // see new_issue: persist while still holding the exclusive lock
+        // so the id allocation and the index persist are atomic
+        db.save_index()?;
 
         Ok(comment_id)
     }
 
-    fn get_issues<'a>(&'a self) -> BoxIter<'a, Result<Issue, Self::Error>> {
-        let db = self.0.read().unwrap();
+    fn set_status(&self, issue_id : u32, status : IssueStatus) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+        let db = self.inner.read().unwrap();
+
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+
+        let issue_path = db.path.join(issue_id.to_string());
+        let mut issue_meta = read_issue_meta(&issue_path)?;
+        issue_meta.status = status;
+        write_issue_meta(&issue_path, &issue_meta)
+    }
+
+    fn add_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+        let db = self.inner.read().unwrap();
+
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+
+        let issue_path = db.path.join(issue_id.to_string());
+        let mut issue_meta = read_issue_meta(&issue_path)?;
+
+        if !issue_meta.labels.iter().any(|l| l == label) {
+            issue_meta.labels.push(label.to_string());
+        }
+
+        write_issue_meta(&issue_path, &issue_meta)
+    }
+
+    fn remove_label(&self, issue_id : u32, label : &str) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+        let db = self.inner.read().unwrap();
+
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+
+        let issue_path = db.path.join(issue_id.to_string());
+        let mut issue_meta = read_issue_meta(&issue_path)?;
+        issue_meta.labels.retain(|l| l != label);
+        write_issue_meta(&issue_path, &issue_meta)
+    }
+
+    fn assign(&self, issue_id : u32, assignee : Option<String>) -> Result<(), Self::Error> {
+        if self.mode == LockMode::ReadOnly {
+            return Err(FsError::ReadOnly);
+        }
+
+        let _flock = FileLock::exclusive(&self.lock_file)?;
+        let db = self.inner.read().unwrap();
+
+        db.comment_count.get(&issue_id).ok_or(FsError::NoIssue(issue_id))?;
+
+        let issue_path = db.path.join(issue_id.to_string());
+        let mut issue_meta = read_issue_meta(&issue_path)?;
+        issue_meta.assignee = assignee;
+        write_issue_meta(&issue_path, &issue_meta)
+    }
+
+    fn get_issues<'a>(&'a self, filter : &IssueFilter) -> BoxIter<'a, Result<Issue, Self::Error>> {
+        let flock = match FileLock::shared(&self.lock_file) {
+            Ok(flock) => flock,
+            Err(err) => return Box::new(std::iter::once(Err(err))),
+        };
+
+        let db = self.inner.read().unwrap();
         let mut path = db.path.to_path_buf();
+        let filter = filter.clone();
 
         let it = (0..db.issue_count).filter_map(move |issue_id| {
-            // bring in the lock
+            // bring in the locks
             let _db = &db;
+            let _flock = &flock;
 
             path.push(issue_id.to_string());
+            let issue_path = path.clone();
+
             path.push("0");
 
+            let content_path = path.clone();
             let file_res = File::open(&path);
 
             path.pop();
@@ -273,47 +1150,77 @@ impl Db for FsDb {
 
             let go = || {
 
-                let meta = file.metadata()?;
+                let fs_meta = file.metadata()?;
+
+                let issue_meta = read_issue_meta(&issue_path)?;
+                if !filter.matches(&issue_meta) {
+                    return Ok(None);
+                }
 
                 let mut buf = String::new();
                 BufReader::new(file).read_line(&mut buf)?;
 
-                Result::<_, FsError>::Ok(Issue{
+                let (created, modified) = match read_sync_meta(&content_path)? {
+                    Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+                    None => (fs_meta.created()?, fs_meta.modified()?),
+                };
+
+                Result::<_, FsError>::Ok(Some(Issue{
                     issue_id,
-                    created : meta.created()?,
-                    modified : meta.modified()?,
+                    created,
+                    modified,
+                    status : issue_meta.status,
+                    labels : issue_meta.labels,
+                    assignee : issue_meta.assignee,
                     content : buf,
-                })
+                }))
             };
 
-            Some(go())
+            go().transpose()
         });
 
         Box::new(it)
     }
 
     fn get_issue(&self, issue_id : u32) -> Result<Issue, Self::Error> {
-        let db = self.0.read().unwrap();
+        let _flock = FileLock::shared(&self.lock_file)?;
+        let db = self.inner.read().unwrap();
 
-        let mut path = db.path.join(issue_id.to_string());
+        let issue_path = db.path.join(issue_id.to_string());
+        let mut path = issue_path.clone();
         path.push("0");
 
-        let mut file = File::open(path)?;
-        let meta = file.metadata()?;
+        let mut file = File::open(&path)?;
+        let fs_meta = file.metadata()?;
 
         let mut buf = String::new();
         file.read_to_string(&mut buf)?;
 
+        let (created, modified) = match read_sync_meta(&path)? {
+            Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+            None => (fs_meta.created()?, fs_meta.modified()?),
+        };
+
+        let issue_meta = read_issue_meta(&issue_path)?;
+
         Ok(Issue{
             issue_id,
-            created : meta.created()?,
-            modified : meta.modified()?,
+            created,
+            modified,
+            status : issue_meta.status,
+            labels : issue_meta.labels,
+            assignee : issue_meta.assignee,
             content : buf,
         })
     }
 
     fn get_issue_comments(&self, issue_id : u32) -> BoxIter<Result<Comment, Self::Error>> {
-        let db = self.0.read().unwrap();
+        let flock = match FileLock::shared(&self.lock_file) {
+            Ok(flock) => flock,
+            Err(err) => return Box::new(std::iter::once(Err(err))),
+        };
+
+        let db = self.inner.read().unwrap();
 
         let mut path = db.path.join(issue_id.to_string());
         let count = match db.comment_count.get(&issue_id) {
@@ -325,10 +1232,12 @@ impl Db for FsDb {
         };
 
         let it = (0..count).filter_map(move |comment_id| {
-            // bring in the lock
+            // bring in the locks
             let _db = &db;
+            let _flock = &flock;
 
             path.push(comment_id.to_string());
+            let content_path = path.clone();
             let file_res = File::open(&path);
             path.pop();
 
@@ -347,11 +1256,16 @@ impl Db for FsDb {
                 let mut buf = String::new();
                 file.read_to_string(&mut buf)?;
 
+                let (created, modified) = match read_sync_meta(&content_path)? {
+                    Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+                    None => (meta.created()?, meta.modified()?),
+                };
+
                 Ok(Comment{
                     issue_id,
                     comment_id,
-                    created : meta.created()?,
-                    modified : meta.modified()?,
+                    created,
+                    modified,
                     content : buf,
                 })
             };
@@ -363,23 +1277,214 @@ impl Db for FsDb {
     }
 
     fn get_issue_comment(&self, issue_id : u32, comment_id : u32) -> Result<Comment, Self::Error> {
-        let db = self.0.read().unwrap();
+        let _flock = FileLock::shared(&self.lock_file)?;
+        let db = self.inner.read().unwrap();
 
         let mut path = db.path.join(issue_id.to_string());
         path.push(comment_id.to_string());
 
-        let mut file = File::open(path)?;
+        let mut file = File::open(&path)?;
         let meta = file.metadata()?;
 
         let mut buf = String::new();
         file.read_to_string(&mut buf)?;
 
+        let (created, modified) = match read_sync_meta(&path)? {
+            Some(sync_meta) => (sync_meta.created, sync_meta.modified),
+            None => (meta.created()?, meta.modified()?),
+        };
+
         Ok(Comment{
             issue_id,
             comment_id,
-            created : meta.created()?,
-            modified : meta.modified()?,
+            created,
+            modified,
             content : buf,
         })
     }
+
+    fn search(&self, query : &str, any : bool) -> Result<Vec<SearchHit>, Self::Error> {
+        let tokens = tokenize(query);
+
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (scores, matched) = {
+            let _flock = FileLock::shared(&self.lock_file)?;
+            let db = self.inner.read().unwrap();
+
+            let mut scores : HashMap<(u32, u32), u32> = HashMap::new();
+            let mut doc_sets : Vec<std::collections::HashSet<(u32, u32)>> = Vec::new();
+
+            for token in &tokens {
+                let mut set = std::collections::HashSet::new();
+
+                if let Some(postings) = db.search_index.get(token) {
+                    for p in postings {
+                        let key = (p.issue_id, p.comment_id);
+                        *scores.entry(key).or_insert(0) += p.tf;
+                        set.insert(key);
+                    }
+                }
+
+                doc_sets.push(set);
+            }
+
+            let matched : Vec<(u32, u32)> = if any {
+                scores.keys().copied().collect()
+            } else {
+                let mut it = doc_sets.into_iter();
+                let mut acc = it.next().unwrap_or_default();
+
+                for set in it {
+                    acc = acc.intersection(&set).copied().collect();
+                }
+
+                acc.into_iter().collect()
+            };
+
+            (scores, matched)
+        };
+
+        let mut hits : Vec<(SearchHit, SystemTime)> = matched.into_iter()
+            .map(|(issue_id, comment_id)| {
+                let modified = self.get_issue_comment(issue_id, comment_id)
+                    .map(|c| c.modified)
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+
+                let hit = SearchHit{
+                    issue_id,
+                    comment_id,
+                    score : scores[&(issue_id, comment_id)],
+                };
+
+                (hit, modified)
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.0.score.cmp(&a.0.score).then_with(|| b.1.cmp(&a.1))
+        });
+
+        Ok(hits.into_iter().map(|(hit, _)| hit).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a fresh `.praiadb` directory under the system temp dir, removed
+    /// on drop so repeated test runs don't pile up state
+    struct TempDb {
+        path : PathBuf,
+    }
+
+    impl TempDb {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("praia-test-{}", rand::random::<u64>()));
+            create_dir(&path).unwrap();
+            Self{path}
+        }
+
+        fn open(&self) -> FsDb {
+            FsDb::new(self.path.clone(), LockMode::ReadWrite).unwrap()
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn import_new_issue_and_comment_round_trips() {
+        let src = TempDb::new();
+        let db = src.open();
+
+        let issue_id = db.new_issue("an issue").unwrap();
+        db.new_comment(issue_id, "a reply").unwrap();
+
+        let records = db.export_records().unwrap();
+        assert_eq!(records.len(), 2);
+
+        let dst = TempDb::new();
+        let other = dst.open();
+        other.import_records(&records).unwrap();
+
+        let issues : Vec<_> = other.get_issues(&IssueFilter::default()).collect::<Result<_, _>>().unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].content, "an issue");
+
+        let comments : Vec<_> = other.get_issue_comments(issues[0].issue_id).collect::<Result<_, _>>().unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[1].content, "a reply");
+    }
+
+    #[test]
+    fn import_skips_records_already_present_by_hash() {
+        let src = TempDb::new();
+        let db = src.open();
+        db.new_issue("an issue").unwrap();
+
+        let records = db.export_records().unwrap();
+
+        // importing a db's own records back into itself must not
+        // duplicate the issue, since the hash is already present
+        db.import_records(&records).unwrap();
+
+        let issues : Vec<_> = db.get_issues(&IssueFilter::default()).collect::<Result<_, _>>().unwrap();
+        assert_eq!(issues.len(), 1);
+    }
+
+    #[test]
+    fn import_skips_comments_whose_issue_is_missing() {
+        let dst = TempDb::new();
+        let other = dst.open();
+
+        let orphan = SyncRecord{
+            issue_hash : 0xdead_beef,
+            comment_id : 1,
+            hash : 0x1234,
+            origin_id : 1,
+            created : SystemTime::now(),
+            modified : SystemTime::now(),
+            content : "orphaned reply".to_string(),
+        };
+
+        other.import_records(&[orphan]).unwrap();
+
+        let issues : Vec<_> = other.get_issues(&IssueFilter::default()).collect::<Result<_, _>>().unwrap();
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn import_renumbers_colliding_local_ids() {
+        let dst = TempDb::new();
+        let other = dst.open();
+
+        // the target already has a local issue 0, so the incoming
+        // remote issue 0 (a distinct stable hash) must be renumbered
+        // rather than clobbering it
+        let local_id = other.new_issue("local issue").unwrap();
+        assert_eq!(local_id, 0);
+
+        let src = TempDb::new();
+        let remote = src.open();
+        remote.new_issue("remote issue").unwrap();
+        let records = remote.export_records().unwrap();
+
+        other.import_records(&records).unwrap();
+
+        let mut issues : Vec<_> = other.get_issues(&IssueFilter::default()).collect::<Result<_, _>>().unwrap();
+        issues.sort_by_key(|i| i.issue_id);
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].issue_id, 0);
+        assert_eq!(issues[0].content, "local issue");
+        assert_eq!(issues[1].issue_id, 1);
+        assert_eq!(issues[1].content, "remote issue");
+    }
 }
@@ -4,11 +4,13 @@ use std::io::{Read, stdin};
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ArgGroup};
-use chrono::{Local, DateTime};
 use serde::Deserialize;
 
 mod db;
-use db::{Db, FsDb};
+mod async_db;
+mod output;
+use db::{Db, FsDb, FsTransport, IssueFilter, IssueStatus, LockMode};
+use output::Format;
 
 #[derive(Parser)]
 #[clap(group(
@@ -21,6 +23,12 @@ struct App {
     config : Option<String>,
     #[clap(short, long)]
     dir : Option<String>,
+
+    /// how to render output: human-readable text, a single JSON array,
+    /// or one JSON object per line for streaming into other tools
+    #[clap(long, value_enum, default_value = "text", global = true)]
+    format : Format,
+
     #[clap(subcommand)]
     command : Option<Commands>,
 }
@@ -30,16 +38,73 @@ struct App {
 enum Commands {
     List{
         issue : Option<u32>,
+
+        /// only list issues with this status
+        #[clap(long)]
+        status : Option<IssueStatus>,
+
+        /// only list issues carrying this label
+        #[clap(long)]
+        label : Option<String>,
+
+        /// only list issues assigned to this person
+        #[clap(long)]
+        assignee : Option<String>,
     },
     Issue,
     Comment {
         issue_id : u32,
     },
+    Search {
+        query : String,
+
+        /// match any query token instead of requiring all of them
+        #[clap(long)]
+        any : bool,
+    },
+    /// fetch records from `upstream` and merge them in
+    Pull,
+    /// send this db's records to `upstream`
+    Push,
+    /// pull, then push, so both sides end up with the union of records
+    Sync,
+    /// set an issue's status
+    Status {
+        issue_id : u32,
+        status : IssueStatus,
+    },
+    /// add a label to an issue
+    Label {
+        issue_id : u32,
+        label : String,
+    },
+    /// remove a label from an issue
+    Unlabel {
+        issue_id : u32,
+        label : String,
+    },
+    /// assign an issue to someone, or unassign it if no one is given
+    Assign {
+        issue_id : u32,
+        assignee : Option<String>,
+    },
 }
 
 impl Default for Commands {
     fn default() -> Self {
-        Self::List{ issue : None }
+        Self::List{ issue : None, status : None, label : None, assignee : None }
+    }
+}
+
+impl Commands {
+    /// whether this command only reads the db, so it can open `FsDb`
+    /// without ever contending for the exclusive write lock
+    fn lock_mode(&self) -> LockMode {
+        match self {
+            Self::List{..} | Self::Search{..} | Self::Push => LockMode::ReadOnly,
+            Self::Issue | Self::Comment{..} | Self::Pull | Self::Sync
+                | Self::Status{..} | Self::Label{..} | Self::Unlabel{..} | Self::Assign{..} => LockMode::ReadWrite,
+        }
     }
 }
 
@@ -95,6 +160,7 @@ fn get_project_dir(config_flag : Option<String>) -> Option<PathBuf> {
 
 fn main() {
     let app = App::parse();
+    let format = app.format;
 
     let config = if let Some(dir) = app.dir {
         Config{
@@ -125,9 +191,10 @@ fn main() {
     let mut db_path = config.path.clone();
     db_path.push(config.db);
 
-    let db = FsDb::new(db_path).unwrap();
+    let command = app.command.unwrap_or_default();
+    let db = FsDb::new(db_path, command.lock_mode()).unwrap();
 
-    match app.command.unwrap_or_default() {
+    match command {
         Commands::Issue => {
             let mut buf = String::new();
             stdin().read_to_string(&mut buf).unwrap();
@@ -138,16 +205,19 @@ fn main() {
             }
 
             let id = db.new_issue(buf.as_str()).unwrap();
-            db.save_index().unwrap();
-            println!("{id}");
+
+            match format.structured() {
+                None => println!("{id}"),
+                Some(structured) => output::print_one(structured, &db.get_issue(id).unwrap()),
+            }
         },
         Commands::Comment{issue_id} => {
             let issue = db.get_issue(issue_id).unwrap();
-            println!(
-                "/{}\t{}",
-                issue.issue_id,
-                issue.content.trim_end()
-            );
+
+            match format.structured() {
+                None => println!("/{}\t{}", issue.issue_id, issue.content.trim_end()),
+                Some(structured) => output::print_one(structured, &issue),
+            }
 
             let mut buf = String::new();
             stdin().read_to_string(&mut buf).unwrap();
@@ -158,39 +228,65 @@ fn main() {
             }
 
             let id = db.new_comment(issue_id , buf.as_str()).unwrap();
-            db.save_index().unwrap();
-            println!("{id}");
+
+            match format.structured() {
+                None => println!("{id}"),
+                Some(structured) => output::print_one(structured, &db.get_issue_comment(issue_id, id).unwrap()),
+            }
         },
-        Commands::List{issue} => {
+        Commands::Pull => {
+            let transport = FsTransport::new(
+                config.upstream.expect("no upstream configured").into()
+            );
+            db.pull(&transport).unwrap();
+        },
+        Commands::Push => {
+            let transport = FsTransport::new(
+                config.upstream.expect("no upstream configured").into()
+            );
+            db.push(&transport).unwrap();
+        },
+        Commands::Sync => {
+            let transport = FsTransport::new(
+                config.upstream.expect("no upstream configured").into()
+            );
+            db.sync(&transport).unwrap();
+        },
+        Commands::Search{query, any} => {
+            let hits = db.search(&query, any).unwrap();
+            for hit in hits {
+                let comment = db.get_issue_comment(hit.issue_id, hit.comment_id).unwrap();
+                println!(
+                    "/{}/{}\t{}\t{}",
+                    hit.issue_id,
+                    hit.comment_id,
+                    hit.score,
+                    comment.content.trim_end()
+                );
+            }
+        },
+        Commands::List{issue, status, label, assignee} => {
             if let Some(issue) = issue {
-                let it = db.get_issue_comments(issue);
-                for comment_res in it {
-                    let comment = comment_res.unwrap();
-                    print!(
-                        "/{}/{}\t{}\n\n",
-                        comment.issue_id,
-                        comment.comment_id,
-                        DateTime::<Local>::from(comment.created).to_rfc2822()
-                    );
-
-                    for line in comment.content.trim_end().lines() {
-                        println!("\t{line}");
-                    }
-
-                    println!("");
-                }
+                let it = db.get_issue_comments(issue).map(|res| res.unwrap());
+                output::print_list(format, it, output::print_comment_text);
             } else {
-                let it = db.get_issues();
-                for issue_res in it {
-                    let issue = issue_res.unwrap();
-                    println!(
-                        "/{}\t{}",
-                        issue.issue_id,
-                        issue.content.trim_end()
-                    );
-                }
+                let filter = IssueFilter{status, label, assignee};
+                let it = db.get_issues(&filter).map(|res| res.unwrap());
+                output::print_list(format, it, output::print_issue_text);
             }
         },
+        Commands::Status{issue_id, status} => {
+            db.set_status(issue_id, status).unwrap();
+        },
+        Commands::Label{issue_id, label} => {
+            db.add_label(issue_id, &label).unwrap();
+        },
+        Commands::Unlabel{issue_id, label} => {
+            db.remove_label(issue_id, &label).unwrap();
+        },
+        Commands::Assign{issue_id, assignee} => {
+            db.assign(issue_id, assignee).unwrap();
+        },
     }
 }
 
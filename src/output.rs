@@ -0,0 +1,97 @@
+//! renders `Issue`/`Comment` values for the CLI according to `--format`:
+//! `text` keeps each command's existing hand-written layout; `json`
+//! collects a whole list into one pretty-printed array; `ndjson` emits
+//! one compact object per line so a large `list` can be streamed into
+//! other tools without buffering the whole result.
+
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::db::{Comment, Issue};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Ndjson,
+}
+
+/// the two formats that serialize through serde, as opposed to `Text`'s
+/// hand-written layout
+#[derive(Debug, Clone, Copy)]
+pub enum Structured {
+    Json,
+    Ndjson,
+}
+
+impl Format {
+    pub fn structured(self) -> Option<Structured> {
+        match self {
+            Format::Text => None,
+            Format::Json => Some(Structured::Json),
+            Format::Ndjson => Some(Structured::Ndjson),
+        }
+    }
+}
+
+/// serialize a single `Issue`/`Comment` for `--format json`/`--format ndjson`
+pub fn print_one<T : Serialize>(format : Structured, value : &T) {
+    match format {
+        Structured::Json => println!(
+            "{}", serde_json::to_string_pretty(value).expect("Issue/Comment always serialize")
+        ),
+        Structured::Ndjson => println!(
+            "{}", serde_json::to_string(value).expect("Issue/Comment always serialize")
+        ),
+    }
+}
+
+/// render a sequence of `Issue`/`Comment` values for `list`; `text_fmt`
+/// is only invoked in `Format::Text` and keeps the existing hand-written
+/// layout for that item
+pub fn print_list<T : Serialize>(format : Format, items : impl Iterator<Item = T>, text_fmt : impl Fn(&T)) {
+    match format {
+        Format::Text => for item in items {
+            text_fmt(&item);
+        },
+        Format::Ndjson => for item in items {
+            println!("{}", serde_json::to_string(&item).expect("Issue/Comment always serialize"));
+        },
+        Format::Json => {
+            let all : Vec<T> = items.collect();
+            println!("{}", serde_json::to_string_pretty(&all).expect("Issue/Comment always serialize"));
+        },
+    }
+}
+
+pub fn print_issue_text(issue : &Issue) {
+    let labels = if issue.labels.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", issue.labels.join(", "))
+    };
+
+    println!(
+        "/{}\t{:?}{}\t{}",
+        issue.issue_id,
+        issue.status,
+        labels,
+        issue.content.trim_end()
+    );
+}
+
+pub fn print_comment_text(comment : &Comment) {
+    print!(
+        "/{}/{}\t{}\n\n",
+        comment.issue_id,
+        comment.comment_id,
+        DateTime::<Local>::from(comment.created).to_rfc2822()
+    );
+
+    for line in comment.content.trim_end().lines() {
+        println!("\t{line}");
+    }
+
+    println!();
+}